@@ -0,0 +1,167 @@
+use std::sync::Arc;
+use anchor_client::solana_sdk::signature::Keypair;
+use solana_client::nonblocking::rpc_client::RpcClient as NonblockingRpcClient;
+use solana_client::rpc_client::RpcClient;
+use tokio::sync::RwLock;
+
+use crate::services::nonce_manager::{NonceManager, TransactionDurability};
+use crate::services::position_tracker::PositionTracker;
+
+/// Bot-wide defaults that the control server's `set_config` method can
+/// live-tune without a restart. New `SwapConfig`s built via
+/// `SwapConfig::for_sell_percentage` pick up the current `slippage`; stacks
+/// built by `default_stack` and `best_execution_stack` pick up the current
+/// `retry_count`.
+#[derive(Debug, Clone)]
+pub struct RuntimeConfig {
+    pub slippage: f64,
+    pub retry_count: u32,
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        Self {
+            slippage: DEFAULT_SLIPPAGE_PERCENT,
+            retry_count: crate::engine::transaction_retry::MAX_RETRIES,
+        }
+    }
+}
+
+/// Long-lived state shared across sell attempts: the wallet, the RPC clients
+/// each attempt sends through, and the subsystems the sell-execution
+/// middleware stack depends on.
+pub struct AppState {
+    pub wallet: Arc<Keypair>,
+    pub rpc_client: Arc<RpcClient>,
+    pub rpc_nonblocking_client: Arc<NonblockingRpcClient>,
+    /// RPC client pointed at a zero-slot landing service, used by
+    /// [`crate::engine::transaction_retry::execute_raydium_sell_attempt`].
+    pub zeroslot_rpc_client: Arc<NonblockingRpcClient>,
+    /// The bot's durable nonce account, if durable-nonce selling is enabled.
+    /// `None` when every `SwapConfig` uses `TransactionDurability::Blockhash`.
+    pub nonce_manager: Option<Arc<NonceManager>>,
+    /// The bot's currently-held positions, queried by the control server's
+    /// `sell_all`/`get_positions` methods.
+    pub position_tracker: Arc<PositionTracker>,
+    runtime_config: RwLock<RuntimeConfig>,
+}
+
+impl AppState {
+    pub fn new(
+        wallet: Arc<Keypair>,
+        rpc_client: Arc<RpcClient>,
+        rpc_nonblocking_client: Arc<NonblockingRpcClient>,
+        zeroslot_rpc_client: Arc<NonblockingRpcClient>,
+    ) -> Self {
+        Self {
+            wallet,
+            rpc_client,
+            rpc_nonblocking_client,
+            zeroslot_rpc_client,
+            nonce_manager: None,
+            position_tracker: Arc::new(PositionTracker::new()),
+            runtime_config: RwLock::new(RuntimeConfig::default()),
+        }
+    }
+
+    /// Enables durable-nonce selling by attaching a [`NonceManager`].
+    /// Callers should have already run `NonceManager::ensure_nonce_account`
+    /// at startup before passing it here.
+    pub fn with_nonce_manager(mut self, nonce_manager: Arc<NonceManager>) -> Self {
+        self.nonce_manager = Some(nonce_manager);
+        self
+    }
+
+    /// Live-tunes the bot's defaults, leaving any field not supplied as-is.
+    /// Used by the control server's `set_config` method.
+    pub async fn update_runtime_config(&self, slippage: Option<f64>, retry_count: Option<u32>) {
+        let mut config = self.runtime_config.write().await;
+        if let Some(slippage) = slippage {
+            config.slippage = slippage;
+        }
+        if let Some(retry_count) = retry_count {
+            config.retry_count = retry_count;
+        }
+    }
+
+    pub async fn runtime_config(&self) -> RuntimeConfig {
+        self.runtime_config.read().await.clone()
+    }
+}
+
+/// Default slippage tolerance, as a percentage, for sells built without an
+/// explicit value (e.g. via `SwapConfig::for_sell_percentage`).
+pub const DEFAULT_SLIPPAGE_PERCENT: f64 = 1.0;
+
+/// How a sell transaction should be landed once signed. Passed to
+/// `new_signed_and_send_with_landing_mode`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionLandingMode {
+    /// Submit via the RPC client's normal `sendTransaction`.
+    Normal,
+    /// Submit via a zero-slot landing service for lower-latency inclusion.
+    Zeroslot,
+}
+
+/// Per-sell configuration threaded through [`crate::engine::sell_middleware`]
+/// and the `execute_*_sell_attempt` functions.
+#[derive(Debug, Clone)]
+pub struct SwapConfig {
+    /// Fraction of the held position to sell (`< 1.0`), or `>= 1.0` to mean
+    /// "sell everything". See `resolve_sell_amount`.
+    pub amount_in: f64,
+    /// Slippage tolerance as a percentage (e.g. `1.0` = 1%).
+    pub slippage: f64,
+    /// Percentile (0.0-1.0) of recent prioritization fees to target; see
+    /// [`crate::services::priority_fee_oracle::PriorityFeeOracle::estimate_unit_price`].
+    pub priority_fee_percentile: f64,
+    /// Per-retry fee escalation factor; see
+    /// [`crate::services::priority_fee_oracle::PriorityFeeOracle::escalate`].
+    pub priority_fee_multiplier: f64,
+    /// Ceiling on the escalated fee, in micro-lamports per compute unit.
+    pub priority_fee_ceiling_micro_lamports: u64,
+    /// Whether to sign against a freshly-fetched recent blockhash or the
+    /// bot's durable nonce. See `resolve_transaction_hash`.
+    pub durability: TransactionDurability,
+    /// How to land the signed transaction. See `execute_pumpfun_sell_attempt`
+    /// and `execute_pumpswap_sell_attempt`.
+    pub landing_mode: TransactionLandingMode,
+}
+
+impl SwapConfig {
+    pub fn new(amount_in: f64, slippage: f64) -> Self {
+        Self {
+            amount_in,
+            slippage,
+            priority_fee_percentile: crate::services::priority_fee_oracle::DEFAULT_FEE_PERCENTILE,
+            priority_fee_multiplier: crate::services::priority_fee_oracle::DEFAULT_ESCALATION_MULTIPLIER,
+            priority_fee_ceiling_micro_lamports: crate::services::priority_fee_oracle::DEFAULT_FEE_CEILING_MICRO_LAMPORTS,
+            durability: TransactionDurability::default(),
+            landing_mode: TransactionLandingMode::Normal,
+        }
+    }
+
+    /// Switches this config to sign against the bot's durable nonce instead
+    /// of a recent blockhash. The caller is responsible for ensuring
+    /// `AppState.nonce_manager` is set before a sell using this config runs.
+    pub fn with_durable_nonce(mut self) -> Self {
+        self.durability = TransactionDurability::DurableNonce;
+        self
+    }
+
+    /// Builds a config for selling `percentage` (0.0-1.0) of a position at
+    /// `app_state`'s current live-tuned slippage (see
+    /// `AppState::update_runtime_config`) - what the control server's `sell`
+    /// and `sell_all` methods use, since they only take a percentage over
+    /// the wire.
+    pub async fn for_sell_percentage(percentage: f64, app_state: &AppState) -> Self {
+        let slippage = app_state.runtime_config().await.slippage;
+        Self::new(percentage, slippage)
+    }
+
+    /// Overrides how the signed transaction gets landed.
+    pub fn with_landing_mode(mut self, landing_mode: TransactionLandingMode) -> Self {
+        self.landing_mode = landing_mode;
+        self
+    }
+}
@@ -0,0 +1,277 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::Colorize;
+
+use crate::common::{
+    config::{AppState, SwapConfig},
+    logger::Logger,
+};
+use crate::engine::sell_middleware::{RetryMiddleware, SellMiddleware, VerifyMiddleware};
+use crate::engine::transaction_parser::TradeInfoFromToken;
+use crate::engine::transaction_retry::{
+    execute_jupiter_fallback_sell,
+    execute_pumpfun_sell_attempt,
+    execute_pumpswap_sell_attempt,
+    execute_raydium_sell_attempt,
+    resolve_sell_amount,
+    SellTransactionResult,
+    RETRY_DELAY,
+};
+use crate::services::jupiter_api::JupiterClient;
+
+/// Quoted price for one venue, normalized to lamports-per-token so venues
+/// with different pool mechanics can be compared directly.
+#[derive(Debug, Clone)]
+struct VenueQuote {
+    venue: String,
+    lamports_per_token: f64,
+}
+
+async fn quote_pumpfun(trade_info: &TradeInfoFromToken, sell_config: &SwapConfig, app_state: &Arc<AppState>) -> Result<f64> {
+    let pump = crate::dex::pump_fun::Pump::new(
+        app_state.rpc_nonblocking_client.clone(),
+        app_state.rpc_client.clone(),
+        app_state.wallet.clone(),
+    );
+    let (_keypair, _instructions, price) = pump.build_swap_from_parsed_data(trade_info, sell_config.clone()).await?;
+    Ok(price)
+}
+
+async fn quote_pumpswap(trade_info: &TradeInfoFromToken, sell_config: &SwapConfig, app_state: &Arc<AppState>) -> Result<f64> {
+    let pump_swap = crate::dex::pump_swap::PumpSwap::new(
+        app_state.wallet.clone(),
+        Some(app_state.rpc_client.clone()),
+        Some(app_state.rpc_nonblocking_client.clone()),
+    );
+    let (_keypair, _instructions, price) = pump_swap.build_swap_from_parsed_data(trade_info, sell_config.clone()).await?;
+    Ok(price)
+}
+
+async fn quote_raydium(trade_info: &TradeInfoFromToken, sell_config: &SwapConfig, app_state: &Arc<AppState>) -> Result<f64> {
+    let raydium = crate::dex::raydium_launchpad::Raydium::new(
+        app_state.wallet.clone(),
+        Some(app_state.rpc_client.clone()),
+        Some(app_state.rpc_nonblocking_client.clone()),
+    );
+    let (_keypair, _instructions, price) = raydium.build_swap_from_parsed_data(trade_info, sell_config.clone()).await?;
+    Ok(price)
+}
+
+async fn quote_jupiter(trade_info: &TradeInfoFromToken, sell_config: &SwapConfig, app_state: &Arc<AppState>) -> Result<f64> {
+    let wallet_pubkey = app_state.wallet.try_pubkey()?;
+    let amount_to_sell = resolve_sell_amount(trade_info, sell_config, app_state, &wallet_pubkey).await?;
+    let jupiter_client = JupiterClient::new(app_state.rpc_nonblocking_client.clone());
+    let expected_sol = jupiter_client.get_quote(
+        &trade_info.mint,
+        amount_to_sell,
+        (sell_config.slippage as u32 * 100) as u64,
+    ).await?;
+    Ok((expected_sol * 1_000_000_000.0) / amount_to_sell as f64)
+}
+
+/// `quote_pumpfun`/`quote_pumpswap`/`quote_raydium` pass through `_price` from
+/// `build_swap_from_parsed_data` as-is, on the assumption that it's already
+/// lamports-per-raw-token like `quote_jupiter`'s quote (derived explicitly
+/// from `resolve_sell_amount`'s raw token units). Nothing in this module can
+/// verify that assumption against those DEXes' own code, so instead
+/// [`retain_unit_consistent_quotes`] catches the failure mode it would cause:
+/// if one venue's quote is on a different basis (e.g. UI/decimal-adjusted
+/// instead of raw), it differs from the rest by at least `10^decimals`
+/// (commonly 10^6 or 10^9) - far more than real price divergence between
+/// venues for the same mint. Outliers by that much are dropped rather than
+/// trusted to `sort_by`.
+const UNIT_MISMATCH_RATIO: f64 = 50.0;
+
+/// Drops any quote whose `lamports_per_token` differs from the median of
+/// `quotes` by more than [`UNIT_MISMATCH_RATIO`], logging why. Protects
+/// against `sort_by` picking a venue that's "best" only because its quote
+/// is on a different unit basis than the others - see the doc comment on
+/// [`UNIT_MISMATCH_RATIO`].
+fn retain_unit_consistent_quotes(quotes: Vec<VenueQuote>, logger: &Logger) -> Vec<VenueQuote> {
+    if quotes.len() < 2 {
+        return quotes;
+    }
+
+    let mut sorted: Vec<f64> = quotes.iter().map(|q| q.lamports_per_token).collect();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let mid = sorted.len() / 2;
+    let median = if sorted.len() % 2 == 0 {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    };
+
+    if median <= 0.0 {
+        return quotes;
+    }
+
+    quotes.into_iter()
+        .filter(|q| {
+            let ratio = (q.lamports_per_token / median).max(median / q.lamports_per_token.max(f64::MIN_POSITIVE));
+            if ratio > UNIT_MISMATCH_RATIO {
+                logger.log(format!(
+                    "⚠️ Dropping {} quote ({:.2} lamports/token): {:.0}x off the median ({:.2}), looks like a unit mismatch rather than a real price difference",
+                    q.venue, q.lamports_per_token, ratio, median
+                ).red().to_string());
+                false
+            } else {
+                true
+            }
+        })
+        .collect()
+}
+
+/// Quotes every viable venue concurrently and ranks them by
+/// lamports-per-token, highest first. Venues that fail to quote (e.g. no
+/// pool exists on that DEX for this mint) are dropped with a logged warning
+/// rather than failing the whole gather, as are quotes that fail the unit-consistency
+/// check in [`retain_unit_consistent_quotes`].
+async fn gather_quotes(trade_info: &TradeInfoFromToken, sell_config: &SwapConfig, app_state: &Arc<AppState>, logger: &Logger) -> Vec<VenueQuote> {
+    let (pumpfun, pumpswap, raydium, jupiter) = tokio::join!(
+        quote_pumpfun(trade_info, sell_config, app_state),
+        quote_pumpswap(trade_info, sell_config, app_state),
+        quote_raydium(trade_info, sell_config, app_state),
+        quote_jupiter(trade_info, sell_config, app_state),
+    );
+
+    let candidates = [
+        ("PumpFun", pumpfun),
+        ("PumpSwap", pumpswap),
+        ("RaydiumLaunchpad", raydium),
+        ("Jupiter", jupiter),
+    ];
+
+    let mut quotes = Vec::new();
+    for (venue, result) in candidates {
+        match result {
+            Ok(lamports_per_token) => {
+                logger.log(format!("💱 Quote {}: {:.2} lamports/token", venue, lamports_per_token).cyan().to_string());
+                quotes.push(VenueQuote { venue: venue.to_string(), lamports_per_token });
+            }
+            Err(e) => {
+                logger.log(format!("⚠️ No quote from {}: {}", venue, e).yellow().to_string());
+            }
+        }
+    }
+
+    let mut quotes = retain_unit_consistent_quotes(quotes, logger);
+    quotes.sort_by(|a, b| b.lamports_per_token.partial_cmp(&a.lamports_per_token).unwrap_or(std::cmp::Ordering::Equal));
+    quotes
+}
+
+async fn send_on_venue(
+    venue: &str,
+    trade_info: &TradeInfoFromToken,
+    sell_config: &SwapConfig,
+    app_state: Arc<AppState>,
+    logger: &Logger,
+    attempt: u32,
+) -> Result<(anchor_client::solana_sdk::signature::Signature, Option<u64>)> {
+    match venue {
+        "PumpFun" => execute_pumpfun_sell_attempt(trade_info, sell_config.clone(), app_state, logger, attempt).await,
+        "PumpSwap" => execute_pumpswap_sell_attempt(trade_info, sell_config.clone(), app_state, logger, attempt).await,
+        "RaydiumLaunchpad" => execute_raydium_sell_attempt(trade_info, sell_config.clone(), app_state, logger, attempt).await,
+        "Jupiter" => execute_jupiter_fallback_sell(trade_info, sell_config, app_state, logger).await.map(|sig| (sig, None)),
+        other => Err(anyhow::anyhow!("Unknown venue: {}", other)),
+    }
+}
+
+/// Best-execution terminal layer: gathers a quote from every viable venue
+/// (the native pool price each DEX's `build_swap_from_parsed_data` already
+/// computes, plus Jupiter's aggregated quote), sends through the venue with
+/// the highest lamports-per-token, and falls through to the next-best venue
+/// if that send fails - rather than routing solely on `trade_info.dex_type`
+/// and treating Jupiter as a last resort.
+pub struct BestExecutionRouter {
+    pub app_state: Arc<AppState>,
+    pub logger: Logger,
+    pub attempt_counter: Arc<AtomicU32>,
+}
+
+impl BestExecutionRouter {
+    pub fn new(app_state: Arc<AppState>, logger: Logger) -> Self {
+        Self { app_state, logger, attempt_counter: Arc::new(AtomicU32::new(1)) }
+    }
+
+    pub fn with_attempt_counter(app_state: Arc<AppState>, logger: Logger, attempt_counter: Arc<AtomicU32>) -> Self {
+        Self { app_state, logger, attempt_counter }
+    }
+}
+
+#[async_trait]
+impl SellMiddleware for BestExecutionRouter {
+    async fn send(&self, trade_info: &TradeInfoFromToken, sell_config: &SwapConfig) -> Result<SellTransactionResult> {
+        let attempt = self.attempt_counter.load(Ordering::SeqCst);
+        let quotes = gather_quotes(trade_info, sell_config, &self.app_state, &self.logger).await;
+
+        if quotes.is_empty() {
+            return Ok(SellTransactionResult {
+                success: false,
+                signature: None,
+                error: Some("No venue returned a quote".to_string()),
+                used_jupiter_fallback: false,
+                attempt_count: 1,
+                last_valid_block_height: None,
+                chosen_venue: None,
+                runner_up_quotes: Vec::new(),
+            });
+        }
+
+        for (i, quote) in quotes.iter().enumerate() {
+            let runner_up_quotes: Vec<(String, f64)> = quotes.iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, q)| (q.venue.clone(), q.lamports_per_token))
+                .collect();
+
+            self.logger.log(format!("🎯 Best execution: routing through {} ({:.2} lamports/token)", quote.venue, quote.lamports_per_token).purple().to_string());
+
+            match send_on_venue(&quote.venue, trade_info, sell_config, self.app_state.clone(), &self.logger, attempt).await {
+                Ok((signature, last_valid_block_height)) => {
+                    return Ok(SellTransactionResult {
+                        success: true,
+                        signature: Some(signature),
+                        error: None,
+                        used_jupiter_fallback: quote.venue == "Jupiter",
+                        attempt_count: 1,
+                        last_valid_block_height,
+                        chosen_venue: Some(quote.venue.clone()),
+                        runner_up_quotes,
+                    });
+                }
+                Err(e) => {
+                    self.logger.log(format!("❌ Send via {} failed, trying next-best venue: {}", quote.venue, e).yellow().to_string());
+                }
+            }
+        }
+
+        Ok(SellTransactionResult {
+            success: false,
+            signature: None,
+            error: Some("All quoted venues failed to send".to_string()),
+            used_jupiter_fallback: false,
+            attempt_count: 1,
+            last_valid_block_height: None,
+            chosen_venue: None,
+            runner_up_quotes: quotes.into_iter().map(|q| (q.venue, q.lamports_per_token)).collect(),
+        })
+    }
+}
+
+/// Builds `Retry(Verify(BestExecution))` - the best-execution counterpart
+/// of [`crate::engine::sell_middleware::default_stack`]. Jupiter is already
+/// one of the venues quoted by [`BestExecutionRouter`], so unlike the
+/// default stack there's no separate fallback layer wrapping it.
+///
+/// Like `default_stack`, the retry count is read from `app_state`'s
+/// live-tuned runtime config rather than hardcoded, so `set_config` affects
+/// this stack too.
+pub async fn best_execution_stack(app_state: Arc<AppState>, logger: Logger) -> RetryMiddleware<VerifyMiddleware<BestExecutionRouter>> {
+    let max_retries = app_state.runtime_config().await.retry_count;
+    let attempt_counter = Arc::new(AtomicU32::new(1));
+    let router = BestExecutionRouter::with_attempt_counter(app_state.clone(), logger.clone(), attempt_counter.clone());
+    let verify = VerifyMiddleware::new(router, app_state, logger.clone());
+    RetryMiddleware::with_attempt_counter(verify, max_retries, RETRY_DELAY, logger, attempt_counter)
+}
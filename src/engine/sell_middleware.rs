@@ -0,0 +1,299 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use anchor_client::solana_sdk::commitment_config::CommitmentLevel;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use colored::Colorize;
+use tokio::time::sleep;
+
+use crate::common::{
+    config::{AppState, SwapConfig},
+    logger::Logger,
+};
+use crate::engine::transaction_parser::{DexType, TradeInfoFromToken};
+use crate::engine::transaction_retry::{
+    execute_jupiter_fallback_sell,
+    execute_pumpfun_sell_attempt,
+    execute_pumpswap_sell_attempt,
+    execute_raydium_sell_attempt,
+    verify_transaction_with_retry,
+    SellTransactionResult,
+    MAX_RETRIES,
+    RETRY_DELAY,
+};
+
+/// A single layer in the sell-execution pipeline.
+///
+/// Layers are composed like ethers-rs `Middleware`: each implementation wraps
+/// an inner layer, optionally does work before/after, and decides whether to
+/// call through. The innermost layer is a terminal router - [`DexRouter`] or
+/// [`crate::engine::best_execution::BestExecutionRouter`] - that builds and
+/// sends the DEX transaction; [`JupiterFallbackMiddleware`] also sends its
+/// own Jupiter transaction when the layer it wraps fails.
+#[async_trait]
+pub trait SellMiddleware: Send + Sync {
+    async fn send(&self, trade_info: &TradeInfoFromToken, sell_config: &SwapConfig) -> Result<SellTransactionResult>;
+}
+
+/// Terminal layer: routes to the DEX indicated by `trade_info.dex_type` and
+/// returns an unverified result (signature present, `success` reflects only
+/// whether the transaction was submitted).
+///
+/// `attempt_counter` is shared with the enclosing [`RetryMiddleware`] so the
+/// priority-fee oracle can escalate its bid on each retry instead of
+/// resubmitting the same fee (see `prepend_priority_fee_instruction` in
+/// `transaction_retry`).
+pub struct DexRouter {
+    pub app_state: Arc<AppState>,
+    pub logger: Logger,
+    pub attempt_counter: Arc<AtomicU32>,
+}
+
+impl DexRouter {
+    pub fn new(app_state: Arc<AppState>, logger: Logger) -> Self {
+        Self { app_state, logger, attempt_counter: Arc::new(AtomicU32::new(1)) }
+    }
+
+    pub fn with_attempt_counter(app_state: Arc<AppState>, logger: Logger, attempt_counter: Arc<AtomicU32>) -> Self {
+        Self { app_state, logger, attempt_counter }
+    }
+}
+
+#[async_trait]
+impl SellMiddleware for DexRouter {
+    async fn send(&self, trade_info: &TradeInfoFromToken, sell_config: &SwapConfig) -> Result<SellTransactionResult> {
+        let attempt = self.attempt_counter.load(Ordering::SeqCst);
+        let result = match trade_info.dex_type {
+            DexType::PumpFun => {
+                execute_pumpfun_sell_attempt(trade_info, sell_config.clone(), self.app_state.clone(), &self.logger, attempt).await
+            }
+            DexType::PumpSwap => {
+                execute_pumpswap_sell_attempt(trade_info, sell_config.clone(), self.app_state.clone(), &self.logger, attempt).await
+            }
+            DexType::RaydiumLaunchpad => {
+                execute_raydium_sell_attempt(trade_info, sell_config.clone(), self.app_state.clone(), &self.logger, attempt).await
+            }
+            _ => {
+                execute_pumpfun_sell_attempt(trade_info, sell_config.clone(), self.app_state.clone(), &self.logger, attempt).await
+            }
+        };
+
+        match result {
+            Ok((signature, last_valid_block_height)) => Ok(SellTransactionResult {
+                success: true,
+                signature: Some(signature),
+                error: None,
+                used_jupiter_fallback: false,
+                attempt_count: 1,
+                last_valid_block_height,
+                chosen_venue: None,
+                runner_up_quotes: Vec::new(),
+            }),
+            Err(e) => Ok(SellTransactionResult {
+                success: false,
+                signature: None,
+                error: Some(e.to_string()),
+                used_jupiter_fallback: false,
+                attempt_count: 1,
+                last_valid_block_height: None,
+                chosen_venue: None,
+                runner_up_quotes: Vec::new(),
+            }),
+        }
+    }
+}
+
+/// Retries the inner layer up to `max_retries` times, sleeping `delay`
+/// between attempts. This is the loop that used to be hardwired into
+/// `execute_normal_sell_with_retry`.
+pub struct RetryMiddleware<S: SellMiddleware> {
+    pub inner: S,
+    pub max_retries: u32,
+    pub delay: std::time::Duration,
+    pub logger: Logger,
+    pub attempt_counter: Arc<AtomicU32>,
+}
+
+impl<S: SellMiddleware> RetryMiddleware<S> {
+    pub fn new(inner: S, logger: Logger) -> Self {
+        Self { inner, max_retries: MAX_RETRIES, delay: RETRY_DELAY, logger, attempt_counter: Arc::new(AtomicU32::new(1)) }
+    }
+
+    pub fn with_retries(inner: S, max_retries: u32, delay: std::time::Duration, logger: Logger) -> Self {
+        Self { inner, max_retries, delay, logger, attempt_counter: Arc::new(AtomicU32::new(1)) }
+    }
+
+    /// Shares `attempt_counter` with a [`DexRouter`] further down the stack
+    /// so it can escalate its priority fee on each retry.
+    pub fn with_attempt_counter(inner: S, max_retries: u32, delay: std::time::Duration, logger: Logger, attempt_counter: Arc<AtomicU32>) -> Self {
+        Self { inner, max_retries, delay, logger, attempt_counter }
+    }
+}
+
+#[async_trait]
+impl<S: SellMiddleware> SellMiddleware for RetryMiddleware<S> {
+    async fn send(&self, trade_info: &TradeInfoFromToken, sell_config: &SwapConfig) -> Result<SellTransactionResult> {
+        let mut last = None;
+
+        for attempt in 1..=self.max_retries {
+            self.attempt_counter.store(attempt, Ordering::SeqCst);
+            self.logger.log(format!("🔄 Sell attempt {}/{} for token: {}", attempt, self.max_retries, trade_info.mint).cyan().to_string());
+
+            match self.inner.send(trade_info, sell_config).await {
+                Ok(mut result) => {
+                    result.attempt_count = attempt;
+                    if result.success {
+                        return Ok(result);
+                    }
+                    self.logger.log(format!("❌ Attempt {} failed: {:?}", attempt, result.error).yellow().to_string());
+                    last = Some(result);
+                }
+                Err(e) => {
+                    self.logger.log(format!("❌ Attempt {} errored: {}", attempt, e).yellow().to_string());
+                    last = Some(SellTransactionResult {
+                        success: false,
+                        signature: None,
+                        error: Some(e.to_string()),
+                        used_jupiter_fallback: false,
+                        attempt_count: attempt,
+                        last_valid_block_height: None,
+                        chosen_venue: None,
+                        runner_up_quotes: Vec::new(),
+                    });
+                }
+            }
+
+            if attempt < self.max_retries {
+                self.logger.log(format!("⏳ Waiting {:?} before retry...", self.delay).yellow().to_string());
+                sleep(self.delay).await;
+            }
+        }
+
+        last.ok_or_else(|| anyhow!("Retry middleware ran zero attempts"))
+    }
+}
+
+/// Confirms the signature returned by the inner layer against the chain,
+/// turning an "unverified submit" into a verified success/failure.
+pub struct VerifyMiddleware<S: SellMiddleware> {
+    pub inner: S,
+    pub app_state: Arc<AppState>,
+    pub max_verify_retries: u32,
+    pub commitment: CommitmentLevel,
+    pub logger: Logger,
+}
+
+impl<S: SellMiddleware> VerifyMiddleware<S> {
+    pub fn new(inner: S, app_state: Arc<AppState>, logger: Logger) -> Self {
+        Self { inner, app_state, max_verify_retries: 5, commitment: CommitmentLevel::Confirmed, logger }
+    }
+
+    pub fn with_commitment(inner: S, app_state: Arc<AppState>, logger: Logger, commitment: CommitmentLevel) -> Self {
+        Self { inner, app_state, max_verify_retries: 5, commitment, logger }
+    }
+}
+
+#[async_trait]
+impl<S: SellMiddleware> SellMiddleware for VerifyMiddleware<S> {
+    async fn send(&self, trade_info: &TradeInfoFromToken, sell_config: &SwapConfig) -> Result<SellTransactionResult> {
+        let mut result = self.inner.send(trade_info, sell_config).await?;
+
+        if !result.success {
+            return Ok(result);
+        }
+
+        let signature = match result.signature {
+            Some(sig) => sig,
+            None => return Ok(result),
+        };
+
+        match verify_transaction_with_retry(&signature, self.app_state.clone(), &self.logger, self.max_verify_retries, self.commitment, result.last_valid_block_height).await {
+            Ok(true) => Ok(result),
+            Ok(false) => {
+                result.success = false;
+                result.error = Some(format!("Transaction verification failed for signature: {}", signature));
+                Ok(result)
+            }
+            Err(e) => {
+                result.success = false;
+                result.error = Some(format!("Verification error: {}", e));
+                Ok(result)
+            }
+        }
+    }
+}
+
+/// Falls through to the Jupiter aggregator API when the inner layer fails.
+/// Mirrors the last-resort behaviour of the old `execute_sell_with_retry_and_fallback`.
+pub struct JupiterFallbackMiddleware<S: SellMiddleware> {
+    pub inner: S,
+    pub app_state: Arc<AppState>,
+    pub logger: Logger,
+}
+
+impl<S: SellMiddleware> JupiterFallbackMiddleware<S> {
+    pub fn new(inner: S, app_state: Arc<AppState>, logger: Logger) -> Self {
+        Self { inner, app_state, logger }
+    }
+}
+
+#[async_trait]
+impl<S: SellMiddleware> SellMiddleware for JupiterFallbackMiddleware<S> {
+    async fn send(&self, trade_info: &TradeInfoFromToken, sell_config: &SwapConfig) -> Result<SellTransactionResult> {
+        let result = self.inner.send(trade_info, sell_config).await?;
+        if result.success {
+            return Ok(result);
+        }
+
+        self.logger.log(format!("🚀 Attempting Jupiter API fallback for token: {}", trade_info.mint).purple().to_string());
+
+        match execute_jupiter_fallback_sell(trade_info, sell_config, self.app_state.clone(), &self.logger).await {
+            Ok(signature) => Ok(SellTransactionResult {
+                success: true,
+                signature: Some(signature),
+                error: None,
+                used_jupiter_fallback: true,
+                attempt_count: result.attempt_count + 1,
+                last_valid_block_height: None,
+                chosen_venue: result.chosen_venue.clone(),
+                runner_up_quotes: result.runner_up_quotes.clone(),
+            }),
+            Err(e) => Ok(SellTransactionResult {
+                success: false,
+                signature: None,
+                error: Some(format!("All sell attempts failed. Last error: {}", e)),
+                used_jupiter_fallback: true,
+                attempt_count: result.attempt_count + 1,
+                last_valid_block_height: None,
+                chosen_venue: result.chosen_venue.clone(),
+                runner_up_quotes: result.runner_up_quotes.clone(),
+            }),
+        }
+    }
+}
+
+/// Builds the default stack used today: `Fallback(Retry(Verify(DexRouter)))`.
+///
+/// Fallback has to be the outermost layer: it only fires once the layer it
+/// wraps returns `success: false`, and a dropped-during-verification
+/// transaction only becomes a `success: false` result once `VerifyMiddleware`
+/// has already run. Putting `JupiterFallbackMiddleware` *inside*
+/// `VerifyMiddleware` (as an earlier version of this stack did) means it
+/// only ever sees `DexRouter`'s unverified submit result, so it can never
+/// trigger for the "submitted fine, then dropped" case - the exact case
+/// this whole retry/verify/fallback stack exists to recover from.
+///
+/// Callers who want a different order or to omit a layer can compose the
+/// middlewares above directly instead of going through this helper.
+///
+/// The retry count is read from `app_state`'s live-tuned runtime config (see
+/// `AppState::update_runtime_config`), so the control server's `set_config`
+/// method takes effect on the next sell rather than being dead plumbing.
+pub async fn default_stack(app_state: Arc<AppState>, logger: Logger) -> JupiterFallbackMiddleware<RetryMiddleware<VerifyMiddleware<DexRouter>>> {
+    let max_retries = app_state.runtime_config().await.retry_count;
+    let attempt_counter = Arc::new(AtomicU32::new(1));
+    let router = DexRouter::with_attempt_counter(app_state.clone(), logger.clone(), attempt_counter.clone());
+    let verify = VerifyMiddleware::new(router, app_state.clone(), logger.clone());
+    let retry = RetryMiddleware::with_attempt_counter(verify, max_retries, RETRY_DELAY, logger.clone(), attempt_counter);
+    JupiterFallbackMiddleware::new(retry, app_state, logger)
+}
@@ -0,0 +1,41 @@
+use anchor_client::solana_sdk::pubkey::Pubkey;
+use anyhow::{anyhow, Result};
+
+/// Which DEX a parsed trade happened on, and therefore which sell path
+/// [`crate::engine::sell_middleware::DexRouter`] should route through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DexType {
+    PumpFun,
+    PumpSwap,
+    RaydiumLaunchpad,
+}
+
+/// A trade parsed off a subscribed transaction, or synthesized directly from
+/// an operator-supplied mint (see [`TradeInfoFromToken::from_mint_and_percentage`]).
+#[derive(Debug, Clone)]
+pub struct TradeInfoFromToken {
+    pub mint: String,
+    pub dex_type: DexType,
+}
+
+impl TradeInfoFromToken {
+    /// Builds a [`TradeInfoFromToken`] for an operator-triggered sell (the
+    /// control server's `sell`/`sell_all` methods) rather than one parsed
+    /// from a subscribed transaction. `percentage` isn't recorded here - it
+    /// belongs on `SwapConfig` - this just validates `mint` and that
+    /// `percentage` lies within 0.0-1.0, and defaults to
+    /// `DexType::PumpFun`, matching `DexRouter`'s own fallback for a trade
+    /// whose DEX isn't otherwise known.
+    pub fn from_mint_and_percentage(mint: &str, percentage: f64) -> Result<Self> {
+        mint.parse::<Pubkey>()
+            .map_err(|e| anyhow!("Invalid token mint address {}: {}", mint, e))?;
+        if !(0.0..=1.0).contains(&percentage) {
+            return Err(anyhow!("percentage must be between 0.0 and 1.0, got {}", percentage));
+        }
+
+        Ok(Self {
+            mint: mint.to_string(),
+            dex_type: DexType::PumpFun,
+        })
+    }
+}
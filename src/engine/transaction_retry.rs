@@ -2,74 +2,143 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 use anyhow::{anyhow, Result};
 use anchor_client::solana_sdk::{
-    pubkey::Pubkey, 
-    signature::{Signature, Keypair}, 
+    pubkey::Pubkey,
+    signature::{Signature, Keypair},
     instruction::Instruction,
     transaction::{VersionedTransaction, Transaction},
     signer::Signer,
     hash::Hash,
+    commitment_config::CommitmentLevel,
 };
+use solana_transaction_status::TransactionConfirmationStatus;
 use spl_associated_token_account::get_associated_token_address;
 use colored::Colorize;
 use tokio::time::sleep;
 use base64;
 
+use anchor_client::solana_sdk::compute_budget::ComputeBudgetInstruction;
+
 use crate::common::{
     config::{AppState, SwapConfig},
     logger::Logger,
 };
 use crate::engine::swap::SwapDirection;
 use crate::services::jupiter_api::JupiterClient;
+use crate::services::priority_fee_oracle::PriorityFeeOracle;
+use crate::services::nonce_manager::TransactionDurability;
 use crate::engine::transaction_parser::TradeInfoFromToken;
 use crate::core::tx;
 
 /// Maximum number of retry attempts for selling transactions
-const MAX_RETRIES: u32 = 3;
+pub(crate) const MAX_RETRIES: u32 = 3;
 
 /// Delay between retry attempts
-const RETRY_DELAY: Duration = Duration::from_secs(2);
+pub(crate) const RETRY_DELAY: Duration = Duration::from_secs(2);
 
 /// Timeout for transaction verification
 const VERIFICATION_TIMEOUT: Duration = Duration::from_secs(30);
 
+/// Initial delay between verification polls; doubles after each poll up to
+/// [`BACKOFF_CAP`].
+const BACKOFF_INITIAL: Duration = Duration::from_millis(200);
+
+/// Ceiling on the exponential poll backoff.
+const BACKOFF_CAP: Duration = Duration::from_secs(2);
+
 /// Result of a selling transaction attempt
-#[derive(Debug)]
+#[derive(Debug, serde::Serialize)]
 pub struct SellTransactionResult {
     pub success: bool,
     pub signature: Option<Signature>,
     pub error: Option<String>,
     pub used_jupiter_fallback: bool,
     pub attempt_count: u32,
+    /// Last block height at which the submitted transaction's blockhash is
+    /// still valid, used by verification to detect a dropped transaction
+    /// instead of waiting out the full timeout. `None` for durable-nonce
+    /// transactions, which don't expire by block height.
+    pub last_valid_block_height: Option<u64>,
+    /// The venue the best-execution router chose to send through, if the
+    /// sell went through [`crate::engine::best_execution::BestExecutionRouter`].
+    pub chosen_venue: Option<String>,
+    /// The venues that were quoted but not chosen, as `(venue, lamports_per_token)`,
+    /// for auditing the routing decision.
+    pub runner_up_quotes: Vec<(String, f64)>,
+}
+
+/// Ranks a confirmation status so it can be compared against a target
+/// [`CommitmentLevel`] (Processed < Confirmed < Finalized).
+fn commitment_satisfied(status: &TransactionConfirmationStatus, target: CommitmentLevel) -> bool {
+    let rank = |s: &TransactionConfirmationStatus| match s {
+        TransactionConfirmationStatus::Processed => 0,
+        TransactionConfirmationStatus::Confirmed => 1,
+        TransactionConfirmationStatus::Finalized => 2,
+    };
+    let target_rank = match target {
+        CommitmentLevel::Processed => 0,
+        CommitmentLevel::Finalized => 2,
+        _ => 1,
+    };
+    rank(status) >= target_rank
 }
 
-/// Enhanced transaction verification with retry logic
+/// Enhanced transaction verification with retry logic.
+///
+/// Polls `get_signature_statuses` with exponential backoff (200ms doubling
+/// to a 2s cap) and only returns `Ok(true)` once the transaction's
+/// `confirmation_status` reaches `commitment`. If `last_valid_block_height`
+/// is given and the transaction still has no status once the chain passes
+/// that height, the transaction is assumed dropped and verification returns
+/// `Ok(false)` immediately rather than waiting out the full timeout.
 pub async fn verify_transaction_with_retry(
     signature: &Signature,
     app_state: Arc<AppState>,
     logger: &Logger,
     max_retries: u32,
+    commitment: CommitmentLevel,
+    last_valid_block_height: Option<u64>,
 ) -> Result<bool> {
     let start_time = Instant::now();
-    
+    let mut backoff = BACKOFF_INITIAL;
+
     for attempt in 1..=max_retries {
         if start_time.elapsed() > VERIFICATION_TIMEOUT {
             logger.log(format!("Transaction verification timeout after {:?}", start_time.elapsed()).yellow().to_string());
             return Ok(false);
         }
 
-        logger.log(format!("Verifying transaction attempt {}/{}: {}", attempt, max_retries, signature));
+        logger.log(format!("Verifying transaction attempt {}/{} (target {:?}): {}", attempt, max_retries, commitment, signature));
 
         match app_state.rpc_nonblocking_client.get_signature_statuses(&[*signature]).await {
             Ok(result) => {
-                if let Some(status_opt) = result.value.get(0) {
-                    if let Some(status) = status_opt {
-                        if status.err.is_none() {
-                            logger.log(format!("✅ Transaction verified successfully: {}", signature).green().to_string());
-                            return Ok(true);
-                        } else {
-                            logger.log(format!("❌ Transaction failed with error: {:?}", status.err).red().to_string());
+                match result.value.get(0).and_then(|status| status.as_ref()) {
+                    Some(status) => {
+                        if let Some(err) = &status.err {
+                            logger.log(format!("❌ Transaction failed with error: {:?}", err).red().to_string());
                             return Ok(false);
                         }
+
+                        if let Some(conf) = &status.confirmation_status {
+                            if commitment_satisfied(conf, commitment) {
+                                logger.log(format!("✅ Transaction verified at {:?}: {}", conf, signature).green().to_string());
+                                return Ok(true);
+                            }
+                            logger.log(format!("⏳ Transaction at {:?}, waiting for {:?}: {}", conf, commitment, signature).yellow().to_string());
+                        }
+                    }
+                    None => {
+                        if let Some(last_valid) = last_valid_block_height {
+                            match app_state.rpc_nonblocking_client.get_block_height().await {
+                                Ok(current_height) if current_height > last_valid => {
+                                    logger.log(format!("❌ Transaction dropped: block height {} exceeds last valid height {}", current_height, last_valid).red().to_string());
+                                    return Ok(false);
+                                }
+                                Ok(_) => {}
+                                Err(e) => {
+                                    logger.log(format!("RPC error fetching block height: {}", e).yellow().to_string());
+                                }
+                            }
+                        }
                     }
                 }
             }
@@ -79,7 +148,8 @@ pub async fn verify_transaction_with_retry(
         }
 
         if attempt < max_retries {
-            sleep(Duration::from_millis(1000)).await;
+            sleep(backoff).await;
+            backoff = (backoff * 2).min(BACKOFF_CAP);
         }
     }
 
@@ -87,7 +157,15 @@ pub async fn verify_transaction_with_retry(
     Ok(false)
 }
 
-/// Execute a selling transaction with retry and Jupiter fallback
+/// Execute a selling transaction through the default middleware stack
+/// (`Fallback(Retry(Verify(DexRouter)))`): up to the live-tuned retry count
+/// (`MAX_RETRIES` unless changed via `AppState::update_runtime_config`)
+/// submit+verify attempts against the routed DEX, falling back to the Jupiter aggregator
+/// only once those are exhausted - matching the fixed flow this function
+/// used to implement inline. Callers who need a different layer order or
+/// want to omit a layer (e.g. skip Jupiter fallback for a position) should
+/// compose [`crate::engine::sell_middleware`] directly instead of calling
+/// this helper.
 pub async fn execute_sell_with_retry_and_fallback(
     trade_info: &TradeInfoFromToken,
     sell_config: SwapConfig,
@@ -97,144 +175,114 @@ pub async fn execute_sell_with_retry_and_fallback(
     let token_mint = &trade_info.mint;
     logger.log(format!("🔄 Starting sell transaction with retry for token: {}", token_mint).cyan().to_string());
 
-    // First, try the normal selling flow with retries
-    match execute_normal_sell_with_retry(trade_info, sell_config.clone(), app_state.clone(), logger).await {
-        Ok(result) => {
-            if result.success {
-                logger.log(format!("✅ Normal sell succeeded on attempt {}", result.attempt_count).green().to_string());
-                return Ok(result);
-            }
-        }
-        Err(e) => {
-            logger.log(format!("❌ Normal sell attempts failed: {}", e).yellow().to_string());
-        }
-    }
+    let stack = crate::engine::sell_middleware::default_stack(app_state, logger.clone()).await;
+    let result = crate::engine::sell_middleware::SellMiddleware::send(&stack, trade_info, &sell_config).await?;
 
-    // If normal selling failed after retries, try Jupiter fallback
-    logger.log(format!("🚀 Attempting Jupiter API fallback for token: {}", token_mint).purple().to_string());
-    
-    match execute_jupiter_fallback_sell(trade_info, &sell_config, app_state.clone(), logger).await {
-        Ok(signature) => {
-            logger.log(format!("✅ Jupiter fallback sell succeeded: {}", signature).green().to_string());
-            Ok(SellTransactionResult {
-                success: true,
-                signature: Some(signature),
-                error: None,
-                used_jupiter_fallback: true,
-                attempt_count: MAX_RETRIES + 1,
-            })
-        }
-        Err(e) => {
-            logger.log(format!("❌ Jupiter fallback sell failed: {}", e).red().to_string());
-            Ok(SellTransactionResult {
-                success: false,
-                signature: None,
-                error: Some(format!("All sell attempts failed. Last error: {}", e)),
-                used_jupiter_fallback: true,
-                attempt_count: MAX_RETRIES + 1,
-            })
-        }
+    if result.success {
+        logger.log(format!("✅ Sell succeeded on attempt {}", result.attempt_count).green().to_string());
+    } else {
+        logger.log(format!("❌ Sell failed: {:?}", result.error).red().to_string());
     }
+
+    Ok(result)
 }
 
-/// Execute normal selling flow with retry logic
-async fn execute_normal_sell_with_retry(
-    trade_info: &TradeInfoFromToken,
-    sell_config: SwapConfig,
+/// Computes the escalated compute-unit price for this attempt and prepends
+/// a `ComputeBudgetInstruction::set_compute_unit_price` instruction so it
+/// applies to the whole transaction. Escalation is `1.5^(attempt - 1)` by
+/// default, capped at `sell_config.priority_fee_ceiling_micro_lamports`.
+async fn prepend_priority_fee_instruction(
+    instructions: &mut Vec<Instruction>,
+    sell_config: &SwapConfig,
     app_state: Arc<AppState>,
     logger: &Logger,
-) -> Result<SellTransactionResult> {
-    let mut last_error = String::new();
-
-    for attempt in 1..=MAX_RETRIES {
-        logger.log(format!("🔄 Normal sell attempt {}/{} for token: {}", attempt, MAX_RETRIES, trade_info.mint).cyan().to_string());
-
-        match execute_single_sell_attempt(trade_info, sell_config.clone(), app_state.clone(), logger).await {
-            Ok(signature) => {
-                // Verify the transaction
-                match verify_transaction_with_retry(&signature, app_state.clone(), logger, 5).await {
-                    Ok(verified) => {
-                        if verified {
-                            logger.log(format!("✅ Normal sell succeeded on attempt {}: {}", attempt, signature).green().to_string());
-                            return Ok(SellTransactionResult {
-                                success: true,
-                                signature: Some(signature),
-                                error: None,
-                                used_jupiter_fallback: false,
-                                attempt_count: attempt,
-                            });
-                        } else {
-                            last_error = format!("Transaction verification failed for signature: {}", signature);
-                            logger.log(format!("❌ Attempt {} failed: {}", attempt, last_error).yellow().to_string());
-                        }
-                    }
-                    Err(e) => {
-                        last_error = format!("Verification error: {}", e);
-                        logger.log(format!("❌ Attempt {} failed: {}", attempt, last_error).yellow().to_string());
-                    }
-                }
-            }
-            Err(e) => {
-                last_error = e.to_string();
-                logger.log(format!("❌ Attempt {} failed: {}", attempt, last_error).yellow().to_string());
-            }
-        }
-
-        if attempt < MAX_RETRIES {
-            logger.log(format!("⏳ Waiting {:?} before retry...", RETRY_DELAY).yellow().to_string());
-            sleep(RETRY_DELAY).await;
-        }
-    }
+    attempt: u32,
+) -> Result<()> {
+    let mut writable_accounts: Vec<Pubkey> = instructions.iter()
+        .flat_map(|ix| ix.accounts.iter())
+        .filter(|meta| meta.is_writable)
+        .map(|meta| meta.pubkey)
+        .collect();
+    writable_accounts.sort_unstable();
+    writable_accounts.dedup();
+
+    let oracle = PriorityFeeOracle::new(app_state, logger.clone());
+    let base_fee = oracle.estimate_unit_price(&writable_accounts, sell_config.priority_fee_percentile).await?;
+    let price = PriorityFeeOracle::escalate(
+        base_fee,
+        attempt,
+        sell_config.priority_fee_multiplier,
+        sell_config.priority_fee_ceiling_micro_lamports,
+    );
 
-    Err(anyhow!("Normal sell failed after {} attempts. Last error: {}", MAX_RETRIES, last_error))
+    logger.log(format!("⛽ Sell attempt {}: bidding {} micro-lamports/CU (base {})", attempt, price, base_fee).cyan().to_string());
+    instructions.insert(0, ComputeBudgetInstruction::set_compute_unit_price(price));
+    Ok(())
 }
 
-/// Execute a single sell attempt using the existing selling logic
-async fn execute_single_sell_attempt(
-    trade_info: &TradeInfoFromToken,
-    sell_config: SwapConfig,
-    app_state: Arc<AppState>,
-    logger: &Logger,
-) -> Result<Signature> {
-    // Determine which DEX to use based on trade info
-    match trade_info.dex_type {
-        crate::engine::transaction_parser::DexType::PumpFun => {
-            execute_pumpfun_sell_attempt(trade_info, sell_config, app_state, logger).await
-        }
-        crate::engine::transaction_parser::DexType::PumpSwap => {
-            execute_pumpswap_sell_attempt(trade_info, sell_config, app_state, logger).await
-        }
-        crate::engine::transaction_parser::DexType::RaydiumLaunchpad => {
-            execute_raydium_sell_attempt(trade_info, sell_config, app_state, logger).await
+/// Resolves the transaction's lifetime anchor per `sell_config.durability`:
+/// either a freshly-fetched recent blockhash, or the bot's durable nonce
+/// (prepending the required `advance_nonce_account` instruction and
+/// re-querying the on-chain nonce value so a nonce consumed by a prior
+/// attempt is never reused).
+///
+/// Returns the hash to sign against plus, for `Blockhash` mode, the last
+/// block height at which it remains valid - used by verification to detect
+/// a dropped transaction. Durable-nonce transactions don't expire by block
+/// height, so that side is `None`.
+async fn resolve_transaction_hash(
+    instructions: &mut Vec<Instruction>,
+    sell_config: &SwapConfig,
+    app_state: &Arc<AppState>,
+) -> Result<(Hash, Option<u64>)> {
+    match sell_config.durability {
+        TransactionDurability::DurableNonce => {
+            let nonce_manager = app_state.nonce_manager.clone()
+                .ok_or_else(|| anyhow!("DurableNonce selected but no nonce manager is configured"))?;
+            let nonce_hash = nonce_manager.get_current_nonce().await?;
+            instructions.insert(0, nonce_manager.advance_instruction());
+            Ok((nonce_hash, None))
         }
-        _ => {
-            // Default to PumpFun for unknown protocols
-            execute_pumpfun_sell_attempt(trade_info, sell_config, app_state, logger).await
+        TransactionDurability::Blockhash => {
+            // Fetch the hash and its last-valid-block-height from the same
+            // RPC call rather than pairing the cached `BlockhashProcessor`
+            // hash with a second, independent call: two separate calls can
+            // each land against a different recent blockhash, so the
+            // recorded height wouldn't necessarily bound the hash actually
+            // signed against, undermining drop detection in
+            // `verify_transaction_with_retry`.
+            let (hash, last_valid_block_height) = app_state.rpc_nonblocking_client
+                .get_latest_blockhash_with_commitment(anchor_client::solana_sdk::commitment_config::CommitmentConfig::confirmed())
+                .await
+                .map_err(|e| anyhow!("Failed to get recent blockhash: {}", e))?;
+            Ok((hash, Some(last_valid_block_height)))
         }
     }
 }
 
 /// Execute PumpFun sell attempt
-async fn execute_pumpfun_sell_attempt(
+pub(crate) async fn execute_pumpfun_sell_attempt(
     trade_info: &TradeInfoFromToken,
     sell_config: SwapConfig,
     app_state: Arc<AppState>,
     logger: &Logger,
-) -> Result<Signature> {
+    attempt: u32,
+) -> Result<(Signature, Option<u64>)> {
     let pump = crate::dex::pump_fun::Pump::new(
         app_state.rpc_nonblocking_client.clone(),
         app_state.rpc_client.clone(),
         app_state.wallet.clone(),
     );
 
-    let (keypair, instructions, _price) = pump.build_swap_from_parsed_data(trade_info, sell_config).await
+    let (keypair, mut instructions, _price) = pump.build_swap_from_parsed_data(trade_info, sell_config.clone()).await
         .map_err(|e| anyhow!("Failed to build PumpFun swap: {}", e))?;
 
-    let recent_blockhash = crate::services::blockhash_processor::BlockhashProcessor::get_latest_blockhash().await
-        .ok_or_else(|| anyhow!("Failed to get recent blockhash"))?;
+    prepend_priority_fee_instruction(&mut instructions, &sell_config, app_state.clone(), logger, attempt).await?;
+
+    let (recent_blockhash, last_valid_block_height) = resolve_transaction_hash(&mut instructions, &sell_config, &app_state).await?;
 
     let signatures = crate::core::tx::new_signed_and_send_with_landing_mode(
-        crate::common::config::TransactionLandingMode::Normal,
+        sell_config.landing_mode,
         &app_state,
         recent_blockhash,
         &keypair,
@@ -249,27 +297,29 @@ async fn execute_pumpfun_sell_attempt(
     // Parse the string signature to Signature type
     let signature = signatures[0].parse::<Signature>()
         .map_err(|e| anyhow!("Failed to parse signature: {}", e))?;
-    Ok(signature)
+    Ok((signature, last_valid_block_height))
 }
 
 /// Execute Raydium sell attempt
-async fn execute_raydium_sell_attempt(
+pub(crate) async fn execute_raydium_sell_attempt(
     trade_info: &TradeInfoFromToken,
     sell_config: SwapConfig,
     app_state: Arc<AppState>,
     logger: &Logger,
-) -> Result<Signature> {
+    attempt: u32,
+) -> Result<(Signature, Option<u64>)> {
     let raydium = crate::dex::raydium_launchpad::Raydium::new(
         app_state.wallet.clone(),
         Some(app_state.rpc_client.clone()),
         Some(app_state.rpc_nonblocking_client.clone()),
     );
 
-    let (keypair, instructions, _price) = raydium.build_swap_from_parsed_data(trade_info, sell_config).await
+    let (keypair, mut instructions, _price) = raydium.build_swap_from_parsed_data(trade_info, sell_config.clone()).await
         .map_err(|e| anyhow!("Failed to build Raydium swap: {}", e))?;
 
-    let recent_blockhash = crate::services::blockhash_processor::BlockhashProcessor::get_latest_blockhash().await
-        .ok_or_else(|| anyhow!("Failed to get recent blockhash"))?;
+    prepend_priority_fee_instruction(&mut instructions, &sell_config, app_state.clone(), logger, attempt).await?;
+
+    let (recent_blockhash, last_valid_block_height) = resolve_transaction_hash(&mut instructions, &sell_config, &app_state).await?;
 
     let signatures = crate::core::tx::new_signed_and_send_zeroslot(
         app_state.zeroslot_rpc_client.clone(),
@@ -286,30 +336,32 @@ async fn execute_raydium_sell_attempt(
     // Parse the string signature to Signature type
     let signature = signatures[0].parse::<Signature>()
         .map_err(|e| anyhow!("Failed to parse signature: {}", e))?;
-    Ok(signature)
+    Ok((signature, last_valid_block_height))
 }
 
 /// Execute PumpSwap sell attempt
-async fn execute_pumpswap_sell_attempt(
+pub(crate) async fn execute_pumpswap_sell_attempt(
     trade_info: &TradeInfoFromToken,
     sell_config: SwapConfig,
     app_state: Arc<AppState>,
     logger: &Logger,
-) -> Result<Signature> {
+    attempt: u32,
+) -> Result<(Signature, Option<u64>)> {
     let pump_swap = crate::dex::pump_swap::PumpSwap::new(
         app_state.wallet.clone(),
         Some(app_state.rpc_client.clone()),
         Some(app_state.rpc_nonblocking_client.clone()),
     );
 
-    let (keypair, instructions, _price) = pump_swap.build_swap_from_parsed_data(trade_info, sell_config).await
+    let (keypair, mut instructions, _price) = pump_swap.build_swap_from_parsed_data(trade_info, sell_config.clone()).await
         .map_err(|e| anyhow!("Failed to build PumpSwap swap: {}", e))?;
 
-    let recent_blockhash = crate::services::blockhash_processor::BlockhashProcessor::get_latest_blockhash().await
-        .ok_or_else(|| anyhow!("Failed to get recent blockhash"))?;
+    prepend_priority_fee_instruction(&mut instructions, &sell_config, app_state.clone(), logger, attempt).await?;
+
+    let (recent_blockhash, last_valid_block_height) = resolve_transaction_hash(&mut instructions, &sell_config, &app_state).await?;
 
     let signatures = crate::core::tx::new_signed_and_send_with_landing_mode(
-        crate::common::config::TransactionLandingMode::Normal,
+        sell_config.landing_mode,
         &app_state,
         recent_blockhash,
         &keypair,
@@ -323,30 +375,23 @@ async fn execute_pumpswap_sell_attempt(
 
     let signature = signatures[0].parse::<Signature>()
         .map_err(|e| anyhow!("Failed to parse signature: {}", e))?;
-    Ok(signature)
+    Ok((signature, last_valid_block_height))
 }
 
-/// Execute Jupiter API fallback sell
-async fn execute_jupiter_fallback_sell(
+/// Resolves how many raw token units to sell: the current held balance,
+/// scaled by `sell_config.amount_in` when it represents a percentage
+/// (< 1.0) rather than "sell everything" (>= 1.0).
+pub(crate) async fn resolve_sell_amount(
     trade_info: &TradeInfoFromToken,
     sell_config: &SwapConfig,
-    app_state: Arc<AppState>,
-    logger: &Logger,
-) -> Result<Signature> {
-    logger.log("🚀 Executing Jupiter API fallback sell".purple().to_string());
-
-    // Get wallet pubkey
-    let wallet_pubkey = app_state.wallet.try_pubkey()
-        .map_err(|e| anyhow!("Failed to get wallet pubkey: {}", e))?;
-
-    // Get token mint pubkey
+    app_state: &Arc<AppState>,
+    wallet_pubkey: &Pubkey,
+) -> Result<u64> {
     let token_pubkey = trade_info.mint.parse::<Pubkey>()
         .map_err(|e| anyhow!("Invalid token mint address: {}", e))?;
 
-    // Get associated token account
-    let ata = get_associated_token_address(&wallet_pubkey, &token_pubkey);
+    let ata = get_associated_token_address(wallet_pubkey, &token_pubkey);
 
-    // Get current token balance
     let token_account = app_state.rpc_nonblocking_client.get_token_account(&ata).await
         .map_err(|e| anyhow!("Failed to get token account: {}", e))?
         .ok_or_else(|| anyhow!("Token account not found"))?;
@@ -358,12 +403,26 @@ async fn execute_jupiter_fallback_sell(
         return Err(anyhow!("No tokens to sell"));
     }
 
-    // Apply sell percentage based on amount_in field (which represents percentage for sells)
-    let amount_to_sell = if sell_config.amount_in >= 1.0 {
+    Ok(if sell_config.amount_in >= 1.0 {
         token_amount
     } else {
         ((token_amount as f64) * sell_config.amount_in) as u64
-    };
+    })
+}
+
+/// Execute Jupiter API fallback sell
+pub(crate) async fn execute_jupiter_fallback_sell(
+    trade_info: &TradeInfoFromToken,
+    sell_config: &SwapConfig,
+    app_state: Arc<AppState>,
+    logger: &Logger,
+) -> Result<Signature> {
+    logger.log("🚀 Executing Jupiter API fallback sell".purple().to_string());
+
+    let wallet_pubkey = app_state.wallet.try_pubkey()
+        .map_err(|e| anyhow!("Failed to get wallet pubkey: {}", e))?;
+
+    let amount_to_sell = resolve_sell_amount(trade_info, sell_config, &app_state, &wallet_pubkey).await?;
 
     logger.log(format!("💱 Selling {} tokens via Jupiter API", amount_to_sell));
 
@@ -387,7 +446,7 @@ async fn execute_jupiter_fallback_sell(
     logger.log(format!("✅ Jupiter transaction sent: {}", signature).green().to_string());
 
     // Verify the transaction
-    match verify_transaction_with_retry(&signature, app_state, logger, 5).await {
+    match verify_transaction_with_retry(&signature, app_state, logger, 5, CommitmentLevel::Confirmed, None).await {
         Ok(verified) => {
             if verified {
                 Ok(signature)
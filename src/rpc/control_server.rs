@@ -0,0 +1,240 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+use jsonrpsee::server::{RpcModule, Server, ServerHandle};
+use jsonrpsee::types::ErrorObjectOwned;
+use serde::{Deserialize, Serialize};
+use subtle::ConstantTimeEq;
+
+use crate::common::config::{AppState, TransactionLandingMode};
+use crate::common::logger::Logger;
+use crate::engine::sell_middleware::default_stack;
+use crate::engine::sell_middleware::SellMiddleware;
+use crate::engine::transaction_parser::TradeInfoFromToken;
+use crate::engine::transaction_retry::SellTransactionResult;
+
+/// Configuration for the embedded control daemon: what to bind to, and the
+/// shared secret callers must present on every request.
+#[derive(Debug, Clone)]
+pub struct ControlServerConfig {
+    pub bind_addr: SocketAddr,
+    pub auth_token: String,
+}
+
+/// A currently-held position, as reported by `get_positions`.
+#[derive(Debug, Serialize)]
+pub struct PositionInfo {
+    pub mint: String,
+    pub token_amount: u64,
+    pub entry_price_sol: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct SellParams {
+    token: String,
+    mint: String,
+    percentage: f64,
+    landing_mode: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SellAllParams {
+    token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GetPositionsParams {
+    token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SetConfigParams {
+    token: String,
+    slippage: Option<f64>,
+    retry_count: Option<u32>,
+}
+
+/// Compares the caller-supplied token against `expected` in constant time,
+/// so a remote caller can't recover the token byte-by-byte from response
+/// timing the way a short-circuiting `==` would leak.
+fn check_auth(token: &str, expected: &str) -> Result<(), ErrorObjectOwned> {
+    if token.as_bytes().ct_eq(expected.as_bytes()).into() {
+        Ok(())
+    } else {
+        Err(ErrorObjectOwned::owned(-32000, "invalid auth token", None::<()>))
+    }
+}
+
+fn internal_error(e: impl std::fmt::Display) -> ErrorObjectOwned {
+    ErrorObjectOwned::owned(-32001, e.to_string(), None::<()>)
+}
+
+/// Builds a failed [`SellTransactionResult`] for one position in `sell_all`,
+/// so a per-position error shows up in that position's slot in the response
+/// instead of aborting the whole call.
+fn failed_result(mint: &str, logger: &Logger, e: impl std::fmt::Display) -> SellTransactionResult {
+    logger.log(format!("❌ control: sell_all skipping {}: {}", mint, e).red().to_string());
+    SellTransactionResult {
+        success: false,
+        signature: None,
+        error: Some(e.to_string()),
+        used_jupiter_fallback: false,
+        attempt_count: 0,
+        last_valid_block_height: None,
+        chosen_venue: None,
+        runner_up_quotes: Vec::new(),
+    }
+}
+
+/// Parses the wire `landing_mode` string into a [`TransactionLandingMode`],
+/// defaulting to `Normal` when the caller doesn't supply one. An explicit
+/// but unrecognized value is an error rather than a silent default, so a
+/// caller that typos e.g. "zero_slot" finds out instead of unknowingly
+/// getting `Normal` behaviour.
+fn parse_landing_mode(landing_mode: Option<&str>) -> Result<TransactionLandingMode, ErrorObjectOwned> {
+    match landing_mode {
+        None => Ok(TransactionLandingMode::Normal),
+        Some("normal") => Ok(TransactionLandingMode::Normal),
+        Some("zeroslot") => Ok(TransactionLandingMode::Zeroslot),
+        Some(other) => Err(ErrorObjectOwned::owned(
+            -32602,
+            format!("unknown landing_mode: {}", other),
+            None::<()>,
+        )),
+    }
+}
+
+/// Starts the embedded JSON-RPC control daemon so an operator can drive the
+/// bot's positions without restarting it: `sell`, `sell_all`,
+/// `get_positions`, `set_config`. Every method takes a `token` field that
+/// must match `config.auth_token`.
+pub async fn start_control_server(
+    app_state: Arc<AppState>,
+    logger: Logger,
+    config: ControlServerConfig,
+) -> Result<ServerHandle> {
+    let server = Server::builder()
+        .build(config.bind_addr)
+        .await
+        .map_err(|e| anyhow!("Failed to bind control server to {}: {}", config.bind_addr, e))?;
+
+    let mut module = RpcModule::new(());
+
+    {
+        let app_state = app_state.clone();
+        let logger = logger.clone();
+        let auth_token = config.auth_token.clone();
+        module.register_async_method("sell", move |params, _ctx| {
+            let app_state = app_state.clone();
+            let logger = logger.clone();
+            let auth_token = auth_token.clone();
+            async move {
+                let params: SellParams = params.parse().map_err(internal_error)?;
+                check_auth(&params.token, &auth_token)?;
+
+                logger.log(format!("📡 control: sell {} ({}%)", params.mint, params.percentage * 100.0).purple().to_string());
+
+                let trade_info = TradeInfoFromToken::from_mint_and_percentage(&params.mint, params.percentage)
+                    .map_err(internal_error)?;
+                let landing_mode = parse_landing_mode(params.landing_mode.as_deref())?;
+                let sell_config = crate::common::config::SwapConfig::for_sell_percentage(params.percentage, &app_state).await
+                    .with_landing_mode(landing_mode);
+
+                let stack = default_stack(app_state.clone(), logger.clone()).await;
+                let result: SellTransactionResult = stack.send(&trade_info, &sell_config).await.map_err(internal_error)?;
+                if result.success && params.percentage >= 1.0 {
+                    app_state.position_tracker.remove_position(&params.mint).await;
+                }
+                Ok::<_, ErrorObjectOwned>(result)
+            }
+        }).map_err(|e| anyhow!("Failed to register sell method: {}", e))?;
+    }
+
+    {
+        let app_state = app_state.clone();
+        let logger = logger.clone();
+        let auth_token = config.auth_token.clone();
+        module.register_async_method("sell_all", move |params, _ctx| {
+            let app_state = app_state.clone();
+            let logger = logger.clone();
+            let auth_token = auth_token.clone();
+            async move {
+                let params: SellAllParams = params.parse().map_err(internal_error)?;
+                check_auth(&params.token, &auth_token)?;
+
+                logger.log("📡 control: sell_all".purple().to_string());
+
+                let positions = app_state.position_tracker.get_all_positions().await;
+                let mut results = Vec::with_capacity(positions.len());
+                for position in positions {
+                    // One bad position shouldn't stop `sell_all` from
+                    // attempting the rest - record the failure for that
+                    // mint in `results` instead of aborting the whole call.
+                    let outcome = match TradeInfoFromToken::from_mint_and_percentage(&position.mint, 1.0) {
+                        Ok(trade_info) => {
+                            let sell_config = crate::common::config::SwapConfig::for_sell_percentage(1.0, &app_state).await;
+                            let stack = default_stack(app_state.clone(), logger.clone()).await;
+                            match stack.send(&trade_info, &sell_config).await {
+                                Ok(result) => result,
+                                Err(e) => failed_result(&position.mint, &logger, e),
+                            }
+                        }
+                        Err(e) => failed_result(&position.mint, &logger, e),
+                    };
+                    if outcome.success {
+                        app_state.position_tracker.remove_position(&position.mint).await;
+                    }
+                    results.push(outcome);
+                }
+                Ok::<_, ErrorObjectOwned>(results)
+            }
+        }).map_err(|e| anyhow!("Failed to register sell_all method: {}", e))?;
+    }
+
+    {
+        let app_state = app_state.clone();
+        let auth_token = config.auth_token.clone();
+        module.register_async_method("get_positions", move |params, _ctx| {
+            let app_state = app_state.clone();
+            let auth_token = auth_token.clone();
+            async move {
+                let params: GetPositionsParams = params.parse().map_err(internal_error)?;
+                check_auth(&params.token, &auth_token)?;
+
+                let positions: Vec<PositionInfo> = app_state.position_tracker.get_all_positions().await
+                    .into_iter()
+                    .map(|p| PositionInfo {
+                        mint: p.mint,
+                        token_amount: p.token_amount,
+                        entry_price_sol: p.entry_price_sol,
+                    })
+                    .collect();
+                Ok::<_, ErrorObjectOwned>(positions)
+            }
+        }).map_err(|e| anyhow!("Failed to register get_positions method: {}", e))?;
+    }
+
+    {
+        let app_state = app_state.clone();
+        let logger = logger.clone();
+        let auth_token = config.auth_token.clone();
+        module.register_async_method("set_config", move |params, _ctx| {
+            let app_state = app_state.clone();
+            let logger = logger.clone();
+            let auth_token = auth_token.clone();
+            async move {
+                let params: SetConfigParams = params.parse().map_err(internal_error)?;
+                check_auth(&params.token, &auth_token)?;
+
+                logger.log(format!("📡 control: set_config {:?}", params).cyan().to_string());
+                app_state.update_runtime_config(params.slippage, params.retry_count).await;
+                Ok::<_, ErrorObjectOwned>(true)
+            }
+        }).map_err(|e| anyhow!("Failed to register set_config method: {}", e))?;
+    }
+
+    let handle = server.start(module);
+    logger.log(format!("📡 Control server listening on {}", config.bind_addr).green().to_string());
+    Ok(handle)
+}
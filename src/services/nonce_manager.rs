@@ -0,0 +1,112 @@
+use std::sync::Arc;
+use anchor_client::solana_sdk::{
+    hash::Hash,
+    instruction::Instruction,
+    nonce::{state::{State as NonceState, Versions as NonceVersions}, system_instruction::advance_nonce_account},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::Transaction,
+};
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+
+use crate::common::{config::AppState, logger::Logger};
+
+/// Selects how a sell transaction sources its "recent blockhash": either a
+/// normal fetched blockhash (expires after ~150 blocks / ~80s, and can go
+/// stale across the retry+verify window) or a durable nonce, which stays
+/// valid until it is advanced on-chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionDurability {
+    Blockhash,
+    DurableNonce,
+}
+
+impl Default for TransactionDurability {
+    fn default() -> Self {
+        TransactionDurability::Blockhash
+    }
+}
+
+/// Size in bytes of a nonce account's data (a `NonceState`), used to look
+/// up the rent-exempt balance the account must be funded with.
+const NONCE_ACCOUNT_SIZE: usize = 80;
+
+/// Owns the bot's durable nonce account and provides the current on-chain
+/// nonce value plus the `advance_nonce_account` instruction needed to
+/// consume it, mirroring Solana's durable-nonce transaction pattern.
+pub struct NonceManager {
+    app_state: Arc<AppState>,
+    logger: Logger,
+    pub nonce_account: Pubkey,
+    pub nonce_authority: Arc<Keypair>,
+}
+
+impl NonceManager {
+    pub fn new(app_state: Arc<AppState>, logger: Logger, nonce_account: Pubkey, nonce_authority: Arc<Keypair>) -> Self {
+        Self { app_state, logger, nonce_account, nonce_authority }
+    }
+
+    /// Fetches the nonce account's current stored value directly from the
+    /// chain. Called fresh before every attempt so a nonce consumed by a
+    /// prior (even still-in-flight) attempt is never reused.
+    pub async fn get_current_nonce(&self) -> Result<Hash> {
+        let account = self.app_state.rpc_nonblocking_client
+            .get_account(&self.nonce_account)
+            .await
+            .map_err(|e| anyhow!("Failed to fetch nonce account {}: {}", self.nonce_account, e))?;
+
+        let versions: NonceVersions = bincode::deserialize(&account.data)
+            .map_err(|e| anyhow!("Failed to deserialize nonce account {}: {}", self.nonce_account, e))?;
+
+        match versions.state() {
+            NonceState::Initialized(data) => Ok(data.blockhash()),
+            NonceState::Uninitialized => Err(anyhow!("Nonce account {} is not initialized", self.nonce_account)),
+        }
+    }
+
+    /// Builds the `advance_nonce_account` instruction that must be the
+    /// first instruction of any transaction spending this durable nonce.
+    pub fn advance_instruction(&self) -> Instruction {
+        advance_nonce_account(&self.nonce_account, &self.nonce_authority.pubkey())
+    }
+
+    /// Creates and initializes the nonce account if it doesn't already
+    /// exist on-chain. Intended to be run once at startup when durable-nonce
+    /// selling is enabled.
+    pub async fn ensure_nonce_account(&self, payer: &Keypair) -> Result<()> {
+        if self.app_state.rpc_nonblocking_client.get_account(&self.nonce_account).await.is_ok() {
+            self.logger.log(format!("🔑 Durable nonce account {} already exists", self.nonce_account).cyan().to_string());
+            return Ok(());
+        }
+
+        self.logger.log(format!("🔑 Creating durable nonce account {}", self.nonce_account).cyan().to_string());
+
+        let rent = self.app_state.rpc_nonblocking_client
+            .get_minimum_balance_for_rent_exemption(NONCE_ACCOUNT_SIZE)
+            .await
+            .map_err(|e| anyhow!("Failed to fetch rent-exempt balance: {}", e))?;
+
+        let instructions = system_instruction::create_nonce_account(
+            &payer.pubkey(),
+            &self.nonce_account,
+            &self.nonce_authority.pubkey(),
+            rent,
+        );
+
+        let recent_blockhash = crate::services::blockhash_processor::BlockhashProcessor::get_latest_blockhash().await
+            .ok_or_else(|| anyhow!("Failed to get recent blockhash"))?;
+
+        let mut tx = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+        tx.sign(&[payer, self.nonce_authority.as_ref()], recent_blockhash);
+
+        let signature = self.app_state.rpc_nonblocking_client
+            .send_and_confirm_transaction(&tx)
+            .await
+            .map_err(|e| anyhow!("Failed to create nonce account: {}", e))?;
+
+        self.logger.log(format!("✅ Durable nonce account created: {}", signature).green().to_string());
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use tokio::sync::RwLock;
+
+/// A currently-held token position: recorded after a buy, removed once a
+/// sell for the full amount has been verified.
+#[derive(Debug, Clone)]
+pub struct Position {
+    pub mint: String,
+    pub token_amount: u64,
+    pub entry_price_sol: f64,
+}
+
+/// Tracks the bot's currently-held positions, so the control server's
+/// `sell_all`/`get_positions` methods have something to enumerate.
+pub struct PositionTracker {
+    positions: RwLock<Vec<Position>>,
+}
+
+impl PositionTracker {
+    pub fn new() -> Self {
+        Self { positions: RwLock::new(Vec::new()) }
+    }
+
+    pub async fn get_all_positions(&self) -> Vec<Position> {
+        self.positions.read().await.clone()
+    }
+
+    pub async fn record_position(&self, position: Position) {
+        self.positions.write().await.push(position);
+    }
+
+    pub async fn remove_position(&self, mint: &str) {
+        self.positions.write().await.retain(|p| p.mint != mint);
+    }
+}
+
+impl Default for PositionTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,70 @@
+use std::sync::Arc;
+use anchor_client::solana_sdk::pubkey::Pubkey;
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+
+use crate::common::{config::AppState, logger::Logger};
+
+/// Default percentile of recent non-zero prioritization fees to target.
+pub const DEFAULT_FEE_PERCENTILE: f64 = 0.75;
+
+/// Default per-retry escalation factor (1.5^(attempt - 1)).
+pub const DEFAULT_ESCALATION_MULTIPLIER: f64 = 1.5;
+
+/// Default ceiling on the escalated fee, in micro-lamports per compute unit.
+pub const DEFAULT_FEE_CEILING_MICRO_LAMPORTS: u64 = 1_000_000;
+
+/// Fallback fee used when the RPC returns no recent samples, in
+/// micro-lamports per compute unit.
+const FALLBACK_FEE_MICRO_LAMPORTS: u64 = 1_000;
+
+/// Queries `getRecentPrioritizationFees` for the writable accounts touched by
+/// a transaction and derives a target compute-unit price from it, analogous
+/// to ethers-rs's gas-oracle middleware but for Solana's priority fee market.
+pub struct PriorityFeeOracle {
+    app_state: Arc<AppState>,
+    logger: Logger,
+}
+
+impl PriorityFeeOracle {
+    pub fn new(app_state: Arc<AppState>, logger: Logger) -> Self {
+        Self { app_state, logger }
+    }
+
+    /// Returns the `percentile`-th (0.0-1.0) micro-lamports-per-CU price
+    /// among the recent non-zero prioritization fee samples for
+    /// `writable_accounts`, or a conservative fallback if there were none.
+    pub async fn estimate_unit_price(&self, writable_accounts: &[Pubkey], percentile: f64) -> Result<u64> {
+        let samples = self.app_state.rpc_nonblocking_client
+            .get_recent_prioritization_fees(writable_accounts)
+            .await
+            .map_err(|e| anyhow!("Failed to fetch recent prioritization fees: {}", e))?;
+
+        let mut fees: Vec<u64> = samples.iter()
+            .map(|s| s.prioritization_fee)
+            .filter(|&fee| fee > 0)
+            .collect();
+
+        if fees.is_empty() {
+            self.logger.log(format!("⚠️ No recent non-zero prioritization fee samples, falling back to {} micro-lamports/CU", FALLBACK_FEE_MICRO_LAMPORTS).yellow().to_string());
+            return Ok(FALLBACK_FEE_MICRO_LAMPORTS);
+        }
+
+        fees.sort_unstable();
+        let index = (((fees.len() - 1) as f64) * percentile.clamp(0.0, 1.0)).round() as usize;
+        let fee = fees[index];
+
+        self.logger.log(format!("⛽ Priority fee oracle: p{:.0} of {} samples = {} micro-lamports/CU", percentile * 100.0, fees.len(), fee).cyan().to_string());
+
+        Ok(fee)
+    }
+
+    /// Escalates a base fee for a retry attempt: `base * multiplier^(attempt - 1)`,
+    /// capped at `ceiling`. `attempt` is 1-indexed, so the first attempt is
+    /// charged the unescalated base fee.
+    pub fn escalate(base_fee: u64, attempt: u32, multiplier: f64, ceiling: u64) -> u64 {
+        let factor = multiplier.powi((attempt.saturating_sub(1)) as i32);
+        let escalated = (base_fee as f64 * factor).round() as u64;
+        escalated.min(ceiling)
+    }
+}